@@ -1,49 +1,225 @@
-use core::panic;
 use std::{
-  collections::BTreeSet,
-  fmt,
-  io::{stdin, Error, ErrorKind, Result},
-  sync::mpsc::{channel, Receiver, TryRecvError},
-  thread,
+  collections::{BTreeSet, HashMap},
+  fs,
+  io::{stdout, Error, ErrorKind, Result, Stdout},
+  path::Path,
   time::Duration,
 };
 
-#[derive(Default, PartialEq, Eq, PartialOrd, Ord)]
+use crossterm::{
+  event::{self, Event, KeyCode, KeyEventKind},
+  execute,
+  terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+  backend::CrosstermBackend,
+  layout::{Constraint, Direction, Layout},
+  style::{Color, Modifier, Style},
+  text::{Line, Span},
+  widgets::{Block, Borders, List, ListItem, Paragraph},
+  Terminal,
+};
+use serde::Deserialize;
+
+/// Path of the optional TOML config file, read relative to the working
+/// directory the binary is launched from.
+const CONFIG_PATH: &str = "elevator.toml";
+
+#[derive(Debug, Clone)]
+struct Config {
+  min: i8,
+  max: i8,
+  tick: Duration,
+  dwell_ticks: u32,
+  labels: HashMap<i8, String>,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Config {
+      min: -2,
+      max: 5,
+      tick: Duration::from_secs(1),
+      dwell_ticks: 1,
+      labels: HashMap::new(),
+    }
+  }
+}
+
+impl Config {
+  /// Loads `path` as TOML, falling back to `Config::default()` when the
+  /// file doesn't exist. A present-but-unparsable file is reported as an
+  /// error rather than silently ignored.
+  fn load(path: &Path) -> Result<Config> {
+    let raw = match fs::read_to_string(path) {
+      Ok(raw) => raw,
+      Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Config::default()),
+      Err(err) => return Err(err),
+    };
+    Config::parse(&raw)
+  }
+
+  /// Parses `raw` as TOML and validates it, split out from `load` so it
+  /// can be tested without touching the filesystem.
+  fn parse(raw: &str) -> Result<Config> {
+    let file: ConfigFile =
+      toml::from_str(raw).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+    let config = Config::from(file);
+    if config.min > config.max {
+      return Err(Error::new(ErrorKind::InvalidData, "config min must not exceed max"));
+    }
+    Ok(config)
+  }
+}
+
+/// Raw shape of `elevator.toml`; every field is optional so a partial file
+/// only overrides what it mentions.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ConfigFile {
+  min: Option<i8>,
+  max: Option<i8>,
+  tick_ms: Option<u64>,
+  dwell_ticks: Option<u32>,
+  labels: HashMap<String, String>,
+}
+
+impl From<ConfigFile> for Config {
+  fn from(file: ConfigFile) -> Self {
+    let default = Config::default();
+    Config {
+      min: file.min.unwrap_or(default.min),
+      max: file.max.unwrap_or(default.max),
+      tick: file.tick_ms.map(Duration::from_millis).unwrap_or(default.tick),
+      dwell_ticks: file.dwell_ticks.unwrap_or(default.dwell_ticks),
+      labels: file
+        .labels
+        .into_iter()
+        .filter_map(|(floor, name)| floor.parse().ok().map(|floor| (floor, name)))
+        .collect(),
+    }
+  }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 struct Floor(i8);
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 enum Dir {
   #[default]
   Up,
   Down,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq, Eq)]
 enum State {
   #[default]
   Stopped,
   Moving(Dir),
+  /// Doors are open and dwelling; see `Elevator::dwell_remaining`.
   Opened,
+  /// Doors are swinging shut after the dwell expired, one tick before the
+  /// car is free to move again.
+  Closing,
+}
+
+/// Scheduling discipline used to decide when a travelling car reverses.
+///
+/// `Look` reverses as soon as there are no more requests ahead of the car
+/// in its current direction. `Scan` always rides out to `MAX`/`MIN` first,
+/// the way a physical car with no "no requests ahead" sensor would.
+#[derive(Debug, Default, Clone, Copy)]
+enum ScheduleMode {
+  #[default]
+  Look,
+  Scan,
 }
 
-#[derive(Default)]
 struct Elevator {
   cur: Floor,
-  stops: BTreeSet<Floor>,
+  /// In-car destinations punched in from inside the cab.
+  dest: BTreeSet<Floor>,
+  /// Hall calls requesting a pickup to go up, keyed by the floor.
+  up_calls: BTreeSet<Floor>,
+  /// Hall calls requesting a pickup to go down, keyed by the floor.
+  down_calls: BTreeSet<Floor>,
   state: State,
   dir: Dir,
+  mode: ScheduleMode,
+  /// Ticks left before the doors close, counting down while `Opened`.
+  dwell_remaining: u32,
+  config: Config,
+}
+
+impl Default for Elevator {
+  fn default() -> Self {
+    Elevator::new(Config::default())
+  }
 }
 
 impl Elevator {
-  const MIN: Floor = Floor(-2);
-  const MAX: Floor = Floor(5);
+  fn new(config: Config) -> Self {
+    Elevator {
+      cur: Floor(0.clamp(config.min, config.max)),
+      dest: BTreeSet::new(),
+      up_calls: BTreeSet::new(),
+      down_calls: BTreeSet::new(),
+      state: State::default(),
+      dir: Dir::default(),
+      mode: ScheduleMode::default(),
+      dwell_remaining: 0,
+      config,
+    }
+  }
+
+  /// How many ticks the doors dwell open once (re)opened; always at least 1.
+  fn dwell_ticks(&self) -> u32 {
+    self.config.dwell_ticks.max(1)
+  }
+
+  /// Ticks left before the doors close, while `Opened`.
+  fn dwell_remaining(&self) -> u32 {
+    self.dwell_remaining
+  }
+
+  fn min(&self) -> Floor {
+    Floor(self.config.min)
+  }
+
+  fn max(&self) -> Floor {
+    Floor(self.config.max)
+  }
 
   fn move_to(&mut self, floor: Floor) -> Result<()> {
     self.validate(&floor)?;
-    if self.stops.is_empty() {
+    if self.idle() {
       self.dir = if floor > self.cur { Dir::Up } else { Dir::Down };
     }
-    self.stops.insert(floor);
+    self.dest.insert(floor);
+    Ok(())
+  }
+
+  fn hall_call(&mut self, floor: Floor, dir: Dir) -> Result<()> {
+    self.validate(&floor)?;
+    match dir {
+      Dir::Up if floor == self.max() => {
+        return Err(Error::new(ErrorKind::InvalidInput, "No up call at top floor"))
+      }
+      Dir::Down if floor == self.min() => {
+        return Err(Error::new(
+          ErrorKind::InvalidInput,
+          "No down call at bottom floor",
+        ))
+      }
+      _ => {}
+    }
+    if self.idle() {
+      self.dir = dir;
+    }
+    match dir {
+      Dir::Up => self.up_calls.insert(floor),
+      Dir::Down => self.down_calls.insert(floor),
+    };
     Ok(())
   }
 
@@ -55,34 +231,96 @@ impl Elevator {
     }
   }
 
-  fn floors(&self) -> impl Iterator<Item = Floor> {
-    let _ = self;
-    (Self::MIN.0..=Self::MAX.0).map(Floor)
+  fn floors(&self) -> impl DoubleEndedIterator<Item = Floor> + ExactSizeIterator {
+    (self.config.min..=self.config.max).map(Floor)
+  }
+
+  /// Display name for `floor`: its configured label, or the bare number.
+  fn label(&self, floor: Floor) -> String {
+    self
+      .config
+      .labels
+      .get(&floor.0)
+      .cloned()
+      .unwrap_or_else(|| floor.0.to_string())
+  }
+
+  fn idle(&self) -> bool {
+    self.dest.is_empty() && self.up_calls.is_empty() && self.down_calls.is_empty()
+  }
+
+  /// Cycles between the `Look` and `Scan` scheduling disciplines.
+  fn toggle_mode(&mut self) {
+    self.mode = match self.mode {
+      ScheduleMode::Look => ScheduleMode::Scan,
+      ScheduleMode::Scan => ScheduleMode::Look,
+    };
+  }
+
+  fn mode(&self) -> ScheduleMode {
+    self.mode
+  }
+
+  /// Whether any pending stop still justifies travelling in `dir`. In
+  /// `Look` mode this must check *every* pending stop, not just hall calls
+  /// headed the same way: a hall call on the other side still needs to be
+  /// passed on the way, so ignoring it would make the car reverse right
+  /// before reaching it and immediately reverse back, livelocking at the
+  /// same floor forever.
+  fn has_more_ahead(&self, dir: Dir) -> bool {
+    match (dir, self.mode) {
+      (Dir::Up, ScheduleMode::Look) => self.pending_stops().any(|f| *f > self.cur),
+      (Dir::Down, ScheduleMode::Look) => self.pending_stops().any(|f| *f < self.cur),
+      (Dir::Up, ScheduleMode::Scan) => self.cur < self.max(),
+      (Dir::Down, ScheduleMode::Scan) => self.cur > self.min(),
+    }
+  }
+
+  /// Clears whatever request brought the car to the current floor, and
+  /// reports whether one was found (so the doors should open).
+  fn stop_here(&mut self) -> bool {
+    let in_car = self.dest.remove(&self.cur);
+    let hall = match self.dir {
+      Dir::Up => self.up_calls.remove(&self.cur),
+      Dir::Down => self.down_calls.remove(&self.cur),
+    };
+    in_car || hall
   }
 
   fn tick(&mut self) {
-    let should_open = self.stops.remove(&self.cur);
+    let should_open = self.stop_here();
     match self.state {
+      // A request for this exact floor while dwelling re-opens the doors
+      // for a fresh dwell; otherwise just count the dwell down.
+      State::Opened if should_open => {
+        self.dwell_remaining = self.dwell_ticks();
+      }
+      State::Opened if self.dwell_remaining > 1 => {
+        self.dwell_remaining -= 1;
+      }
+      State::Opened => {
+        self.state = State::Closing;
+      }
       _ if should_open => {
         self.state = State::Opened;
+        self.dwell_remaining = self.dwell_ticks();
       }
-      _ if self.stops.is_empty() => {
+      _ if self.idle() => {
         self.state = State::Stopped;
       }
-      State::Stopped | State::Opened => {
-        self.state = State::Moving(self.dir);
-      }
-      State::Moving(Dir::Up) => {
-        self.cur = Floor(self.cur.0 + 1);
-        if self.cur >= *self.stops.last().unwrap() {
-          self.dir = Dir::Down;
+      State::Closing | State::Stopped => {
+        if !self.has_more_ahead(self.dir) {
+          self.dir = self.dir.reverse();
         }
+        self.state = State::Moving(self.dir);
       }
-      State::Moving(Dir::Down) => {
-        self.cur = Floor(self.cur.0 - 1);
-        if self.cur <= *self.stops.first().unwrap() {
-          self.dir = Dir::Up;
+      State::Moving(_) => {
+        if self.has_more_ahead(self.dir) {
+          self.cur = Floor(self.cur.0 + if matches!(self.dir, Dir::Up) { 1 } else { -1 });
+        } else {
+          self.dir = self.dir.reverse();
         }
+        self.state = State::Moving(self.dir);
       }
     }
   }
@@ -98,70 +336,493 @@ impl Elevator {
   fn state(&self) -> &State {
     &self.state
   }
+
+  /// All floors the car still owes a stop: in-car destinations plus every
+  /// hall call regardless of direction. Used both for display and by
+  /// `has_more_ahead` to decide whether a `Look` sweep should keep going.
+  fn pending_stops(&self) -> impl Iterator<Item = &Floor> {
+    self.dest.iter().chain(&self.up_calls).chain(&self.down_calls)
+  }
 }
 
-impl fmt::Display for Elevator {
-  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    let s = if self.is_opened() { "*" } else { "v" };
-    let space = " ".repeat(6 * self.idx() + 2);
-    let state = self.state();
-    let floors = self
-      .floors()
-      .map(|Floor(floor)| format!("[{floor:>2} ]"))
-      .collect::<Vec<_>>()
-      .join(" ");
-    write!(f, "{space}{s}\n{floors}\n\nState: {state:?}")
+impl Dir {
+  fn reverse(self) -> Self {
+    match self {
+      Dir::Up => Dir::Down,
+      Dir::Down => Dir::Up,
+    }
   }
 }
 
-fn main() -> Result<()> {
-  let floor_channel = floor_channel();
-  let elevator = &mut Elevator::default();
-  let error = &mut None;
-  loop {
-    match floor_channel.try_recv() {
-      Ok(Ok(floor)) => *error = elevator.move_to(floor).err(),
-      Ok(Err(err)) => *error = Some(err),
-      Err(TryRecvError::Disconnected) => panic!(),
-      Err(TryRecvError::Empty) => (),
+enum Input {
+  Digit(char),
+  Enter,
+  Up,
+  Down,
+  HallUp,
+  HallDown,
+  ToggleMode,
+  Quit,
+}
+
+// crossterm::event::poll (chunk0-1) already replaced the blocking-thread,
+// fixed-1s-sleep loop this was meant to fix, so there's no "disconnected"
+// case to model — Tick only distinguishes a key arriving from the wait
+// simply timing out.
+enum Tick {
+  Request(Input),
+  Elapsed,
+}
+
+/// Source of `Tick`s driving the simulation, split out from `main` so the
+/// stepping logic in `advance` can run against a scripted `FakeDriver`
+/// instead of a real terminal.
+trait Driver {
+  fn poll(&mut self, tick_interval: Duration) -> Result<Tick>;
+}
+
+struct CrosstermDriver;
+
+impl Driver for CrosstermDriver {
+  fn poll(&mut self, tick_interval: Duration) -> Result<Tick> {
+    if !event::poll(tick_interval)? {
+      return Ok(Tick::Elapsed);
+    }
+    let Event::Key(key) = event::read()? else {
+      return Ok(Tick::Elapsed);
+    };
+    if key.kind != KeyEventKind::Press {
+      return Ok(Tick::Elapsed);
+    }
+    Ok(match key.code {
+      KeyCode::Char(d) if d.is_ascii_digit() || d == '-' => Tick::Request(Input::Digit(d)),
+      KeyCode::Enter => Tick::Request(Input::Enter),
+      KeyCode::Up => Tick::Request(Input::Up),
+      KeyCode::Down => Tick::Request(Input::Down),
+      KeyCode::Char('u') => Tick::Request(Input::HallUp),
+      KeyCode::Char('d') => Tick::Request(Input::HallDown),
+      KeyCode::Char('m') => Tick::Request(Input::ToggleMode),
+      KeyCode::Esc | KeyCode::Char('q') => Tick::Request(Input::Quit),
+      _ => Tick::Elapsed,
+    })
+  }
+}
+
+/// A scripted `Driver` for deterministic tests: hands out queued `Tick`s in
+/// order, falling back to `Tick::Elapsed` (a bare simulation step) once the
+/// script runs dry — so `tick N times` is just `N` calls with nothing queued.
+#[cfg(test)]
+#[derive(Default)]
+struct FakeDriver {
+  script: std::collections::VecDeque<Tick>,
+}
+
+#[cfg(test)]
+impl FakeDriver {
+  /// Queues key presses that type `floor` into the pending-digits buffer
+  /// and confirm it with Enter, the same as a real keypress sequence would.
+  fn queue_floor_request(&mut self, floor: Floor) {
+    for digit in floor.0.to_string().chars() {
+      self.script.push_back(Tick::Request(Input::Digit(digit)));
     }
-    elevator.tick();
-    draw_ui(error.as_ref(), elevator)?;
-    thread::sleep(Duration::from_secs(1));
+    self.script.push_back(Tick::Request(Input::Enter));
+  }
+
+  /// Queues key presses that type `floor` then confirm it as a hall call
+  /// headed `dir`, the same as `queue_floor_request` but ending in
+  /// `HallUp`/`HallDown` instead of `Enter`.
+  fn queue_hall_call(&mut self, floor: Floor, dir: Dir) {
+    for digit in floor.0.to_string().chars() {
+      self.script.push_back(Tick::Request(Input::Digit(digit)));
+    }
+    self.script.push_back(Tick::Request(match dir {
+      Dir::Up => Input::HallUp,
+      Dir::Down => Input::HallDown,
+    }));
+  }
+}
+
+#[cfg(test)]
+impl Driver for FakeDriver {
+  fn poll(&mut self, _tick_interval: Duration) -> Result<Tick> {
+    Ok(self.script.pop_front().unwrap_or(Tick::Elapsed))
   }
 }
 
-fn draw_ui(error: Option<&Error>, elevator: &Elevator) -> Result<()> {
-  std::process::Command::new("clear").status()?;
-  #[rustfmt::skip]
-  println!(r#"
-Elevator
+fn main() -> Result<()> {
+  let (config, config_error) = match Config::load(Path::new(CONFIG_PATH)) {
+    Ok(config) => (config, None),
+    Err(err) => (Config::default(), Some(err)),
+  };
+  let mut terminal = setup_terminal()?;
+  let result = run(&mut terminal, &mut CrosstermDriver, config, config_error);
+  teardown_terminal(terminal)?;
+  result
+}
+
+fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
+  enable_raw_mode()?;
+  let mut out = stdout();
+  execute!(out, EnterAlternateScreen)?;
+  Terminal::new(CrosstermBackend::new(out))
+}
 
-Enter floor number to move elevator
+fn teardown_terminal(mut terminal: Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
+  disable_raw_mode()?;
+  execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+  terminal.show_cursor()
+}
 
-{elevator}
-  "#);
-  if let Some(error) = error {
-    eprintln!("Error: {error}\n");
+fn run(
+  terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+  driver: &mut impl Driver,
+  config: Config,
+  mut config_error: Option<Error>,
+) -> Result<()> {
+  let tick_interval = config.tick;
+  let elevator = &mut Elevator::new(config);
+  let error = &mut config_error;
+  let pending_digits = &mut String::new();
+  let mut last_frame = render_key(elevator, error.as_ref(), pending_digits);
+  draw_ui(terminal, error.as_ref(), elevator, pending_digits)?;
+  while !advance(elevator, error, pending_digits, driver, tick_interval)? {
+    let frame = render_key(elevator, error.as_ref(), pending_digits);
+    if frame != last_frame {
+      draw_ui(terminal, error.as_ref(), elevator, pending_digits)?;
+      last_frame = frame;
+    }
   }
   Ok(())
 }
 
-fn scan_floor() -> Result<Result<Floor>> {
-  let input = &mut String::new();
-  stdin().read_line(input)?;
-  let floor = input
-    .trim()
-    .parse()
-    .map(Floor)
-    .map_err(|err| Error::new(ErrorKind::InvalidInput, err));
-  Ok(floor)
+/// Applies one `Tick` from `driver` to `elevator`: a received request is
+/// applied immediately, an elapsed tick advances the simulation one step.
+/// Returns `true` once the user asks to quit.
+fn advance(
+  elevator: &mut Elevator,
+  error: &mut Option<Error>,
+  pending_digits: &mut String,
+  driver: &mut impl Driver,
+  tick_interval: Duration,
+) -> Result<bool> {
+  match driver.poll(tick_interval)? {
+    Tick::Request(Input::Quit) => return Ok(true),
+    Tick::Request(Input::Digit(d)) => pending_digits.push(d),
+    Tick::Request(Input::Enter) => {
+      *error = pending_digits
+        .parse()
+        .map_err(|err| Error::new(ErrorKind::InvalidInput, err))
+        .and_then(|floor| elevator.move_to(Floor(floor)))
+        .err();
+      pending_digits.clear();
+    }
+    Tick::Request(Input::Up) => *error = elevator.move_to(elevator.max()).err(),
+    Tick::Request(Input::Down) => *error = elevator.move_to(elevator.min()).err(),
+    Tick::Request(Input::HallUp) => {
+      *error = pending_digits
+        .parse()
+        .map_err(|err| Error::new(ErrorKind::InvalidInput, err))
+        .and_then(|floor| elevator.hall_call(Floor(floor), Dir::Up))
+        .err();
+      pending_digits.clear();
+    }
+    Tick::Request(Input::HallDown) => {
+      *error = pending_digits
+        .parse()
+        .map_err(|err| Error::new(ErrorKind::InvalidInput, err))
+        .and_then(|floor| elevator.hall_call(Floor(floor), Dir::Down))
+        .err();
+      pending_digits.clear();
+    }
+    Tick::Request(Input::ToggleMode) => elevator.toggle_mode(),
+    Tick::Elapsed => elevator.tick(),
+  }
+  Ok(false)
+}
+
+/// A cheap fingerprint of everything `draw_ui` renders, so the main loop can
+/// skip redrawing on ticks/keys that didn't actually change the display.
+fn render_key(elevator: &Elevator, error: Option<&Error>, pending_digits: &str) -> String {
+  format!(
+    "{:?}|{:?}|{:?}|{}|{}|{}",
+    elevator.cur.0,
+    elevator.state(),
+    elevator.mode(),
+    elevator.dwell_remaining(),
+    elevator.pending_stops().count(),
+    pending_digits,
+  ) + &error.map(ToString::to_string).unwrap_or_default()
+}
+
+fn draw_ui(
+  terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+  error: Option<&Error>,
+  elevator: &Elevator,
+  pending_digits: &str,
+) -> Result<()> {
+  terminal
+    .draw(|frame| {
+      let area = frame.size();
+      let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+          Constraint::Min(3),
+          Constraint::Length(1),
+          Constraint::Length(1),
+        ])
+        .split(area);
+
+      let cur_idx = elevator.idx();
+      let rows = elevator
+        .floors()
+        .enumerate()
+        .rev()
+        .map(|(i, floor)| {
+          let label = format!("[{:>2}]", elevator.label(floor));
+          if i == cur_idx {
+            let marker = if elevator.is_opened() { "*" } else { "v" };
+            ListItem::new(format!("{label} {marker}"))
+              .style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow))
+          } else {
+            ListItem::new(label)
+          }
+        })
+        .collect::<Vec<_>>();
+      let shaft = List::new(rows).block(Block::default().title("Elevator").borders(Borders::ALL));
+      frame.render_widget(shaft, chunks[0]);
+
+      let stops = elevator
+        .pending_stops()
+        .map(|&floor| elevator.label(floor))
+        .collect::<Vec<_>>()
+        .join(", ");
+      let door_countdown = if elevator.is_opened() {
+        format!(" ({} left)", elevator.dwell_remaining())
+      } else {
+        String::new()
+      };
+      let status = Line::from(vec![
+        Span::raw(format!("State: {:?}{door_countdown}", elevator.state())),
+        Span::raw(format!("  Mode: {:?}", elevator.mode())),
+        Span::raw("  Stops: "),
+        Span::raw(if stops.is_empty() { "-".into() } else { stops }),
+        Span::raw("  Enter floor: "),
+        Span::raw(pending_digits.to_owned()),
+      ]);
+      frame.render_widget(Paragraph::new(status), chunks[1]);
+
+      let error_line = match error {
+        Some(err) => Line::from(Span::styled(
+          format!("Error: {err}"),
+          Style::default().fg(Color::Red),
+        )),
+        None => Line::from(""),
+      };
+      frame.render_widget(Paragraph::new(error_line), chunks[2]);
+    })
+    .map(|_| ())
 }
 
-fn floor_channel() -> Receiver<Result<Floor>> {
-  let (tx, rx) = channel();
-  thread::spawn(move || loop {
-    tx.send(scan_floor().unwrap()).unwrap();
-  });
-  rx
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn config_file_overrides_all_fields() {
+    let file: ConfigFile = toml::from_str(
+      r#"
+      min = -3
+      max = 10
+      tick_ms = 250
+      dwell_ticks = 4
+
+      [labels]
+      0 = "Lobby"
+      "#,
+    )
+    .unwrap();
+    let config = Config::from(file);
+    assert_eq!(config.min, -3);
+    assert_eq!(config.max, 10);
+    assert_eq!(config.tick, Duration::from_millis(250));
+    assert_eq!(config.dwell_ticks, 4);
+    assert_eq!(config.labels.get(&0), Some(&"Lobby".to_string()));
+  }
+
+  #[test]
+  fn config_parse_rejects_min_greater_than_max() {
+    let err = Config::parse("min = 5\nmax = 1\n").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+  }
+
+  #[test]
+  fn config_parse_rejects_malformed_toml() {
+    let err = Config::parse("min = [this isn't valid toml").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+  }
+
+  #[test]
+  fn up_call_at_top_floor_is_rejected() {
+    let mut elevator = Elevator::default();
+    let max = elevator.max();
+    let err = elevator.hall_call(max, Dir::Up).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+  }
+
+  #[test]
+  fn down_call_at_bottom_floor_is_rejected() {
+    let mut elevator = Elevator::default();
+    let min = elevator.min();
+    let err = elevator.hall_call(min, Dir::Down).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+  }
+
+  /// A bundle of the bits `advance` needs across calls, so a test can
+  /// `step()` repeatedly without re-threading every argument by hand.
+  #[derive(Default)]
+  struct Sim {
+    elevator: Elevator,
+    error: Option<Error>,
+    pending_digits: String,
+    driver: FakeDriver,
+  }
+
+  impl Sim {
+    fn step(&mut self) -> Result<bool> {
+      advance(
+        &mut self.elevator,
+        &mut self.error,
+        &mut self.pending_digits,
+        &mut self.driver,
+        Duration::ZERO,
+      )
+    }
+  }
+
+  #[test]
+  fn requesting_current_floor_opens_immediately() -> Result<()> {
+    let mut sim = Sim::default();
+    sim.driver.queue_floor_request(Floor(0));
+    sim.step()?; // Digit('0')
+    sim.step()?; // Enter: applies move_to(0)
+    sim.step()?; // elapsed tick: already there, so straight to Opened
+    assert_eq!(sim.elevator.state(), &State::Opened);
+    Ok(())
+  }
+
+  #[test]
+  fn travels_to_a_requested_floor_and_opens() -> Result<()> {
+    let mut sim = Sim::default();
+    sim.driver.queue_floor_request(Floor(2));
+    sim.step()?; // Digit('2')
+    sim.step()?; // Enter: applies move_to(2)
+    sim.step()?; // Stopped -> Moving(Up), still at floor 0
+    sim.step()?; // cur 0 -> 1
+    sim.step()?; // cur 1 -> 2
+    sim.step()?; // arrived: Opened
+    assert_eq!(sim.elevator.cur, Floor(2));
+    assert_eq!(sim.elevator.state(), &State::Opened);
+    Ok(())
+  }
+
+  #[test]
+  fn request_arriving_mid_travel_is_picked_up_on_the_way() -> Result<()> {
+    let mut sim = Sim::default();
+    sim.driver.queue_floor_request(Floor(5));
+    sim.step()?; // Digit('5')
+    sim.step()?; // Enter: applies move_to(5)
+    sim.step()?; // Stopped -> Moving(Up), still at floor 0
+    sim.step()?; // cur 0 -> 1
+    sim.driver.queue_floor_request(Floor(3));
+    sim.step()?; // Digit('3')
+    sim.step()?; // Enter: applies move_to(3) mid-travel, cur still 1
+    sim.step()?; // cur 1 -> 2
+    sim.step()?; // cur 2 -> 3
+    sim.step()?; // the nearer request is served first: Opened at 3
+    assert_eq!(sim.elevator.cur, Floor(3));
+    assert_eq!(sim.elevator.state(), &State::Opened);
+    Ok(())
+  }
+
+  #[test]
+  fn hall_call_registers_a_directional_pickup() -> Result<()> {
+    let mut sim = Sim::default();
+    sim.driver.queue_hall_call(Floor(2), Dir::Up);
+    sim.step()?; // Digit('2')
+    sim.step()?; // HallUp: registers hall_call(2, Up)
+    assert!(sim.elevator.up_calls.contains(&Floor(2)));
+    Ok(())
+  }
+
+  #[test]
+  fn opposite_side_hall_call_below_the_car_is_eventually_serviced() -> Result<()> {
+    let mut elevator = Elevator::default();
+    elevator.hall_call(Floor(-2), Dir::Up)?;
+    for _ in 0..20 {
+      if elevator.is_opened() {
+        break;
+      }
+      elevator.tick();
+    }
+    assert_eq!(elevator.cur, Floor(-2));
+    assert_eq!(elevator.state(), &State::Opened);
+    assert!(elevator.up_calls.is_empty());
+    Ok(())
+  }
+
+  #[test]
+  fn opposite_side_hall_call_above_a_parked_car_is_eventually_serviced() -> Result<()> {
+    let mut elevator = Elevator::default();
+    elevator.move_to(Floor(2))?;
+    for _ in 0..6 {
+      elevator.tick();
+    }
+    assert_eq!(elevator.state(), &State::Stopped);
+    elevator.hall_call(Floor(4), Dir::Down)?;
+    for _ in 0..20 {
+      if elevator.is_opened() {
+        break;
+      }
+      elevator.tick();
+    }
+    assert_eq!(elevator.cur, Floor(4));
+    assert_eq!(elevator.state(), &State::Opened);
+    assert!(elevator.down_calls.is_empty());
+    Ok(())
+  }
+
+  #[test]
+  fn toggle_mode_switches_between_look_and_scan() -> Result<()> {
+    let mut sim = Sim::default();
+    assert!(matches!(sim.elevator.mode(), ScheduleMode::Look));
+    sim.driver.script.push_back(Tick::Request(Input::ToggleMode));
+    sim.step()?;
+    assert!(matches!(sim.elevator.mode(), ScheduleMode::Scan));
+    Ok(())
+  }
+
+  #[test]
+  fn doors_dwell_for_the_configured_number_of_ticks_before_closing() -> Result<()> {
+    let mut sim = Sim {
+      elevator: Elevator::new(Config {
+        dwell_ticks: 3,
+        ..Config::default()
+      }),
+      ..Sim::default()
+    };
+    sim.driver.queue_floor_request(Floor(0));
+    sim.step()?; // Digit('0')
+    sim.step()?; // Enter: applies move_to(0)
+    sim.step()?; // elapsed tick: already there, Opened, dwell_remaining == 3
+    assert_eq!(sim.elevator.state(), &State::Opened);
+    assert_eq!(sim.elevator.dwell_remaining(), 3);
+    sim.step()?; // dwell_remaining -> 2, still Opened
+    assert_eq!(sim.elevator.state(), &State::Opened);
+    assert_eq!(sim.elevator.dwell_remaining(), 2);
+    sim.step()?; // dwell_remaining -> 1, still Opened
+    assert_eq!(sim.elevator.dwell_remaining(), 1);
+    sim.step()?; // dwell exhausted: Closing
+    assert_eq!(sim.elevator.state(), &State::Closing);
+    Ok(())
+  }
 }